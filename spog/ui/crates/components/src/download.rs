@@ -1,11 +1,15 @@
 use anyhow::Result;
+use cyclonedx_bom::models::bom::SpecVersion;
 use cyclonedx_bom::models::component::Classification;
+use cyclonedx_bom::models::dependency::Dependency;
+use cyclonedx_bom::models::hash::HashAlgorithm;
 use patternfly_yew::prelude::*;
 use serde_json::json;
-use spdx_rs::models::{PrimaryPackagePurpose, SPDX};
+use spdx_rs::models::{Algorithm, PrimaryPackagePurpose, Relationship, RelationshipType, SPDX};
 use spog_ui_backend::{use_backend, ApplyAccessToken};
 use spog_ui_utils::analytics::use_wrap_tracking;
 use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
     rc::Rc,
     str::FromStr,
 };
@@ -47,6 +51,20 @@ pub fn sbom_kebab_dropdown(props: &SbomKebabDropdownProperties) -> Html {
 
     let local_file = use_state_eq(|| None);
 
+    // The `spdx` prop is only ever populated for JSON-parsed documents. When the source is a
+    // locally loaded tag-value (`.spdx`) file, parse it ourselves so CycloneDX generation works
+    // identically for both serializations.
+    let spdx = use_memo((props.spdx.clone(), props.sbom_source.clone()), |(spdx, sbom_source)| {
+        if spdx.is_some() {
+            return spdx.clone();
+        }
+
+        match sbom_source {
+            SbomSource::LOCAL(data, filename) => parse_local_tag_value_spdx(data, filename).map(Rc::new),
+            SbomSource::URL(_) => None,
+        }
+    });
+
     let on_download_sbom_click = use_callback((props.sbom_source.clone(), access_token.clone()), {
         let local_file = local_file.clone();
         move |_, (sbom_source, access_token)| match sbom_source {
@@ -62,26 +80,30 @@ pub fn sbom_kebab_dropdown(props: &SbomKebabDropdownProperties) -> Html {
         }
     });
 
-    let on_generate_cyclonedx_click = use_callback(
-        (
-            props.spdx.clone(),
-            local_file.clone(),
-            props.id.clone(),
-        ),
-        move |_, (spdx, local_file, id)| {
-            if let Some(spdx) = spdx.clone() {
-                match generate_cyclonedx(spdx.as_ref()) {
-                    Ok(data) => {
-                        let filename = format!("{}-cyclonedx.json", safe_filename(&id));
-                        local_file.set(Some((Rc::new(data), filename)));
-                    }
-                    Err(err) => {
-                        log::error!("Failed to generate CycloneDX SBOM: {err}");
+    let new_generate_cyclonedx_click = |version: SpecVersion| {
+        use_callback(
+            ((*spdx).clone(), local_file.clone(), props.id.clone()),
+            move |_, (spdx, local_file, id)| {
+                if let Some(spdx) = spdx.clone() {
+                    match generate_cyclonedx(spdx.as_ref(), version) {
+                        Ok(data) => {
+                            let filename =
+                                format!("{}-cyclonedx-{}.json", safe_filename(id), spec_version_label(version));
+                            local_file.set(Some((Rc::new(data), filename)));
+                        }
+                        Err(err) => {
+                            log::error!("Failed to generate CycloneDX SBOM: {err}");
+                        }
                     }
                 }
-            }
-        },
-    );
+            },
+        )
+    };
+
+    let on_generate_cyclonedx_v13_click = new_generate_cyclonedx_click(SpecVersion::V1_3);
+    let on_generate_cyclonedx_v14_click = new_generate_cyclonedx_click(SpecVersion::V1_4);
+    let on_generate_cyclonedx_v15_click = new_generate_cyclonedx_click(SpecVersion::V1_5);
+    let on_generate_cyclonedx_v16_click = new_generate_cyclonedx_click(SpecVersion::V1_6);
 
     let on_download_licenses_click = use_callback(
         (props.id.clone(), access_token.clone()),
@@ -129,8 +151,13 @@ pub fn sbom_kebab_dropdown(props: &SbomKebabDropdownProperties) -> Html {
                 icon={props.dropdown_icon.clone()}
         >
             <MenuAction onclick={on_download_sbom_click}>{"Download SBOM"}</MenuAction>
-            { for props.spdx.is_some().then(|| html_nested!(
-                <MenuAction onclick={on_generate_cyclonedx_click}>{"Generate CycloneDX"}</MenuAction>
+            { for spdx.is_some().then(|| html_nested!(
+                <Dropdown text="Generate CycloneDX" variant={MenuToggleVariant::Plain}>
+                    <MenuAction onclick={on_generate_cyclonedx_v13_click}>{"1.3"}</MenuAction>
+                    <MenuAction onclick={on_generate_cyclonedx_v14_click}>{"1.4"}</MenuAction>
+                    <MenuAction onclick={on_generate_cyclonedx_v15_click}>{"1.5"}</MenuAction>
+                    <MenuAction onclick={on_generate_cyclonedx_v16_click}>{"1.6"}</MenuAction>
+                </Dropdown>
             )) }
             <MenuAction onclick={on_download_licenses_click}>{"Download License Report"}</MenuAction>
         </Dropdown>
@@ -170,20 +197,95 @@ pub fn download(props: &DownloadProperties) -> Html {
     )
 }
 
-fn generate_cyclonedx(spdx: &SPDX) -> Result<String> {
+/// Parses a locally loaded SBOM payload as tag-value SPDX, for use when it doesn't look like JSON
+/// (or its filename suggests tag-value, e.g. `.spdx`).
+fn parse_local_tag_value_spdx(data: &str, filename: &str) -> Option<SPDX> {
+    if !should_attempt_tag_value_parse(data, filename) {
+        return None;
+    }
+
+    spdx_rs::parsers::spdx_from_tag_value(data).ok()
+}
+
+/// A `.spdx` filename always warrants a tag-value attempt; otherwise only bother when the
+/// payload doesn't already look like JSON.
+fn should_attempt_tag_value_parse(data: &str, filename: &str) -> bool {
+    filename.ends_with(".spdx") || !data.trim_start().starts_with('{')
+}
+
+/// Maps the SPDX checksum algorithms MD5, SHA1, SHA256 and SHA512 to their CycloneDX
+/// equivalents; every other algorithm (e.g. SHA224 or SHA384) maps to `None`.
+fn spdx_algorithm_to_hash_algorithm(algorithm: &Algorithm) -> Option<HashAlgorithm> {
+    match algorithm {
+        Algorithm::MD5 => Some(HashAlgorithm::MD5),
+        Algorithm::SHA1 => Some(HashAlgorithm::SHA1),
+        Algorithm::SHA256 => Some(HashAlgorithm::SHA256),
+        Algorithm::SHA512 => Some(HashAlgorithm::SHA512),
+        _ => None,
+    }
+}
+
+/// Groups `DEPENDS_ON`/`CONTAINS`/`DYNAMIC_LINK`/`STATIC_LINK` SPDX relationships into CycloneDX
+/// `Dependency` entries, skipping the document node and any element not in `known_refs`.
+/// Refs are kept in sorted order so the same SPDX document always produces the same BOM bytes.
+fn build_dependencies(relationships: &[Relationship], known_refs: &HashSet<String>) -> Vec<Dependency> {
+    const SPDX_DOCUMENT_ID: &str = "SPDXRef-DOCUMENT";
+
+    let mut dependency_targets: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+
+    for relationship in relationships {
+        if !matches!(
+            relationship.relationship_type,
+            RelationshipType::DependsOn | RelationshipType::Contains | RelationshipType::DynamicLink | RelationshipType::StaticLink
+        ) {
+            continue;
+        }
+
+        if relationship.spdx_element_id == SPDX_DOCUMENT_ID || relationship.related_spdx_element == SPDX_DOCUMENT_ID {
+            continue;
+        }
+
+        if !known_refs.contains(&relationship.spdx_element_id) || !known_refs.contains(&relationship.related_spdx_element) {
+            continue;
+        }
+
+        dependency_targets
+            .entry(relationship.spdx_element_id.clone())
+            .or_default()
+            .insert(relationship.related_spdx_element.clone());
+    }
+
+    dependency_targets
+        .into_iter()
+        .map(|(dependency_ref, targets)| Dependency {
+            dependency_ref,
+            dependencies: targets.into_iter().collect(),
+        })
+        .collect()
+}
+
+fn generate_cyclonedx(spdx: &SPDX, version: SpecVersion) -> Result<String> {
     use cyclonedx_bom::external_models::{
         normalized_string::NormalizedString,
         uri::Purl,
     };
+    use cyclonedx_bom::external_models::date_time::DateTime;
     use cyclonedx_bom::models::{
-        bom::{Bom, SpecVersion},
+        bom::Bom,
         component::{Component, Components},
+        dependency::Dependencies,
+        hash::{Hash, Hashes},
+        license::{LicenseChoice, Licenses},
+        metadata::Metadata,
+        organization::{OrganizationalContact, OrganizationalEntity},
+        tool::{Tool, Tools},
     };
 
     let mut bom = Bom::default();
-    bom.spec_version = SpecVersion::V1_4;
+    bom.spec_version = version;
 
     let mut components = Vec::new();
+    let mut bom_refs = HashSet::new();
 
     for package in &spdx.package_information {
         let classification = package
@@ -222,6 +324,40 @@ fn generate_cyclonedx(spdx: &SPDX) -> Result<String> {
             }
         }
 
+        if let Some(expression) = spdx_concrete_license(&package.package_license_concluded.to_string())
+            .or_else(|| spdx_concrete_license(&package.package_license_declared.to_string()))
+        {
+            component.licenses = Some(Licenses(vec![LicenseChoice::Expression(NormalizedString::new(&expression))]));
+        }
+
+        let hashes: Vec<Hash> = package
+            .package_checksum
+            .iter()
+            .filter_map(|checksum| {
+                let alg = spdx_algorithm_to_hash_algorithm(&checksum.algorithm)?;
+                Some(Hash {
+                    alg,
+                    content: checksum.value.clone().into(),
+                })
+            })
+            .collect();
+
+        if !hashes.is_empty() {
+            component.hashes = Some(Hashes(hashes));
+        }
+
+        if let Some(supplier) = package.package_supplier.as_deref().and_then(spdx_concrete_party) {
+            component.supplier = Some(OrganizationalEntity {
+                name: Some(NormalizedString::new(&supplier)),
+                ..Default::default()
+            });
+        }
+
+        if let Some(originator) = package.package_originator.as_deref().and_then(spdx_concrete_party) {
+            component.author = Some(NormalizedString::new(&originator));
+        }
+
+        bom_refs.insert(package.package_spdx_identifier.clone());
         components.push(component);
     }
 
@@ -231,12 +367,74 @@ fn generate_cyclonedx(spdx: &SPDX) -> Result<String> {
 
     bom.components = Some(Components(components));
 
+    let dependencies = build_dependencies(&spdx.relationships, &bom_refs);
+    if !dependencies.is_empty() {
+        bom.dependencies = Some(Dependencies(dependencies));
+    }
+
+    let creation_info = &spdx.document_creation_information.creation_info;
+
+    let mut tools = Vec::new();
+    let mut authors = Vec::new();
+    for creator in &creation_info.creators {
+        if let Some(tool) = creator.strip_prefix("Tool:") {
+            let tool = tool.trim();
+            let (name, version) = tool.rsplit_once('-').unwrap_or((tool, ""));
+            tools.push(Tool {
+                name: Some(NormalizedString::new(name)),
+                version: (!version.is_empty()).then(|| NormalizedString::new(version)),
+                ..Default::default()
+            });
+        } else if let Some(name) = creator.strip_prefix("Organization:").or_else(|| creator.strip_prefix("Person:")) {
+            authors.push(OrganizationalContact {
+                name: Some(NormalizedString::new(name.trim())),
+                ..Default::default()
+            });
+        }
+    }
+
+    let mut metadata = Metadata::default();
+    metadata.timestamp = DateTime::from_str(&creation_info.created.to_rfc3339()).ok();
+    // CycloneDX 1.5 added an object form for `metadata.tools` (`{components, services}`), but
+    // the legacy array-of-`Tool` form stayed valid (deprecated, not removed) for backward
+    // compatibility, so populate it the same way across every spec version we support here.
+    if !tools.is_empty() {
+        metadata.tools = Some(Tools(tools));
+    }
+    // `metadata.authors` (plural, structured) was only added in CycloneDX 1.6; earlier
+    // schema versions have no equivalent slot, so drop it rather than emit an invalid document.
+    if !authors.is_empty() && matches!(version, SpecVersion::V1_6) {
+        metadata.authors = Some(authors);
+    }
+
+    let document_info = &spdx.document_creation_information;
+    let mut subject = Component::new(
+        Classification::Application,
+        &document_info.document_name,
+        "",
+        Some(document_info.spdx_document_namespace.clone()),
+    );
+    subject.version = None;
+    metadata.component = Some(subject);
+
+    bom.metadata = Some(metadata);
+
     let mut output = Vec::new();
-    bom.output_as_json(&mut output, SpecVersion::V1_4)?;
+    bom.output_as_json(&mut output, version)?;
 
     Ok(String::from_utf8(output)?)
 }
 
+fn spec_version_label(version: SpecVersion) -> &'static str {
+    match version {
+        SpecVersion::V1_3 => "1.3",
+        SpecVersion::V1_4 => "1.4",
+        SpecVersion::V1_5 => "1.5",
+        SpecVersion::V1_6 => "1.6",
+        _ => "1.4",
+    }
+}
+
 fn spdx_purpose_to_classification(purpose: &PrimaryPackagePurpose) -> Classification {
     match purpose {
         PrimaryPackagePurpose::Application => Classification::Application,
@@ -254,6 +452,33 @@ fn spdx_purpose_to_classification(purpose: &PrimaryPackagePurpose) -> Classifica
     }
 }
 
+/// Returns `None` for an empty value and the SPDX sentinel values `NOASSERTION`/`NONE`, and
+/// the trimmed expression otherwise.
+fn spdx_concrete_license(expression: &str) -> Option<String> {
+    let expression = expression.trim();
+    match expression {
+        "" | "NOASSERTION" | "NONE" => None,
+        expression => Some(expression.to_string()),
+    }
+}
+
+/// Strips the SPDX `Organization:`/`Person:`/`Tool:` prefix from a supplier or
+/// originator value, returning `None` for the sentinel values `NOASSERTION`/`NONE`.
+fn spdx_concrete_party(value: &str) -> Option<String> {
+    let value = value.trim();
+    match value {
+        "NOASSERTION" | "NONE" => None,
+        value => Some(
+            ["Organization:", "Person:", "Tool:"]
+                .iter()
+                .find_map(|prefix| value.strip_prefix(prefix))
+                .map(str::trim)
+                .unwrap_or(value)
+                .to_string(),
+        ),
+    }
+}
+
 fn safe_filename(input: &str) -> String {
     let sanitized: String = input
         .chars()
@@ -324,3 +549,99 @@ pub fn inline_download(props: &LocalDownloadButtonProperties) -> Html {
         }
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spdx_concrete_license_drops_sentinel_values() {
+        assert_eq!(spdx_concrete_license("NOASSERTION"), None);
+        assert_eq!(spdx_concrete_license("NONE"), None);
+        assert_eq!(spdx_concrete_license(""), None);
+        assert_eq!(spdx_concrete_license("  NOASSERTION  "), None);
+    }
+
+    #[test]
+    fn spdx_concrete_license_keeps_concrete_expressions() {
+        assert_eq!(spdx_concrete_license("MIT"), Some("MIT".to_string()));
+        assert_eq!(spdx_concrete_license(" Apache-2.0 "), Some("Apache-2.0".to_string()));
+    }
+
+    #[test]
+    fn spdx_concrete_party_drops_sentinel_values() {
+        assert_eq!(spdx_concrete_party("NOASSERTION"), None);
+        assert_eq!(spdx_concrete_party("NONE"), None);
+    }
+
+    #[test]
+    fn spdx_concrete_party_strips_known_prefixes() {
+        assert_eq!(spdx_concrete_party("Organization: Foo Inc."), Some("Foo Inc.".to_string()));
+        assert_eq!(spdx_concrete_party("Person: Jane Doe"), Some("Jane Doe".to_string()));
+    }
+
+    #[test]
+    fn spdx_concrete_party_keeps_unprefixed_values_as_is() {
+        assert_eq!(spdx_concrete_party("Jane Doe"), Some("Jane Doe".to_string()));
+    }
+
+    #[test]
+    fn spdx_algorithm_to_hash_algorithm_maps_supported_algorithms() {
+        assert_eq!(spdx_algorithm_to_hash_algorithm(&Algorithm::MD5), Some(HashAlgorithm::MD5));
+        assert_eq!(spdx_algorithm_to_hash_algorithm(&Algorithm::SHA1), Some(HashAlgorithm::SHA1));
+        assert_eq!(spdx_algorithm_to_hash_algorithm(&Algorithm::SHA256), Some(HashAlgorithm::SHA256));
+        assert_eq!(spdx_algorithm_to_hash_algorithm(&Algorithm::SHA512), Some(HashAlgorithm::SHA512));
+    }
+
+    #[test]
+    fn spdx_algorithm_to_hash_algorithm_ignores_unsupported_algorithms() {
+        assert_eq!(spdx_algorithm_to_hash_algorithm(&Algorithm::SHA384), None);
+    }
+
+    fn relationship(from: &str, to: &str, relationship_type: RelationshipType) -> Relationship {
+        Relationship {
+            spdx_element_id: from.to_string(),
+            related_spdx_element: to.to_string(),
+            relationship_type,
+            comment: None,
+        }
+    }
+
+    #[test]
+    fn build_dependencies_groups_and_sorts_targets() {
+        let known_refs: HashSet<String> = ["SPDXRef-A", "SPDXRef-B", "SPDXRef-C"]
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        let relationships = vec![
+            relationship("SPDXRef-A", "SPDXRef-C", RelationshipType::DependsOn),
+            relationship("SPDXRef-A", "SPDXRef-B", RelationshipType::Contains),
+            relationship("SPDXRef-A", "SPDXRef-B", RelationshipType::DependsOn),
+        ];
+
+        let dependencies = build_dependencies(&relationships, &known_refs);
+
+        assert_eq!(dependencies.len(), 1);
+        assert_eq!(dependencies[0].dependency_ref, "SPDXRef-A");
+        assert_eq!(dependencies[0].dependencies, vec!["SPDXRef-B".to_string(), "SPDXRef-C".to_string()]);
+    }
+
+    #[test]
+    fn build_dependencies_skips_document_node_and_unknown_refs() {
+        let known_refs: HashSet<String> = ["SPDXRef-A", "SPDXRef-B"].into_iter().map(str::to_string).collect();
+        let relationships = vec![
+            relationship("SPDXRef-DOCUMENT", "SPDXRef-A", RelationshipType::DependsOn),
+            relationship("SPDXRef-A", "SPDXRef-Unknown", RelationshipType::DependsOn),
+            relationship("SPDXRef-A", "SPDXRef-B", RelationshipType::Describes),
+        ];
+
+        assert!(build_dependencies(&relationships, &known_refs).is_empty());
+    }
+
+    #[test]
+    fn should_attempt_tag_value_parse_detects_json_payloads() {
+        assert!(!should_attempt_tag_value_parse("{\"spdxVersion\": \"SPDX-2.3\"}", "doc.json"));
+        assert!(should_attempt_tag_value_parse("SPDXVersion: SPDX-2.3", "doc.txt"));
+        assert!(should_attempt_tag_value_parse("{\"spdxVersion\": \"SPDX-2.3\"}", "doc.spdx"));
+    }
+}